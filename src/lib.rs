@@ -26,47 +26,479 @@
 
 use std::io::Write;
 
+/// An HTTP version, as written at the start of a response status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpVersion {
+    Http10,
+    Http11,
+}
+
+impl HttpVersion {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            HttpVersion::Http10 => "HTTP/1.0",
+            HttpVersion::Http11 => "HTTP/1.1",
+        }
+    }
+}
+
+/// Get the canonical reason phrase for a well-known status code, or `""` if `code`
+/// isn't one of them.
+fn reason_phrase(code: u16) -> &'static str {
+    match code {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        410 => "Gone",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
+}
+
+/// Well-known HTTP header field names, for use with `HeaderLines::field`.
+pub mod field {
+    pub const CONTENT_LENGTH: &str = "Content-Length";
+    pub const CONTENT_TYPE: &str = "Content-Type";
+    pub const CONNECTION: &str = "Connection";
+    pub const DATE: &str = "Date";
+    pub const HOST: &str = "Host";
+    pub const LOCATION: &str = "Location";
+    pub const SERVER: &str = "Server";
+    pub const TRANSFER_ENCODING: &str = "Transfer-Encoding";
+}
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] =
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Format `time` as an IMF-fixdate, the form required for a `Date` header field by
+/// [RFC 7231 §7.1.1.1](https://tools.ietf.org/html/rfc7231#section-7.1.1.1), e.g.
+/// `"Sun, 06 Nov 1994 08:49:37 GMT"`.
+fn imf_fixdate(time: std::time::SystemTime) -> String {
+    let secs = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+    let days = (secs / 86400) as i64;
+    let time_of_day = secs % 86400;
+
+    let (year, month, day) = civil_from_days(days);
+    let weekday = WEEKDAYS[(days + 4).rem_euclid(7) as usize];
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        weekday,
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        time_of_day / 3600,
+        (time_of_day % 3600) / 60,
+        time_of_day % 60,
+    )
+}
+
+/// Convert a day count since the Unix epoch into a (year, month, day) civil date, using
+/// Howard Hinnant's
+/// [civil_from_days](https://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+/// algorithm.
+fn civil_from_days(z: i64) -> (i64, i64, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as i64;
+
+    (if month <= 2 { y + 1 } else { y }, month, day)
+}
+
+/// Write a `name: value` header field line into `line`, shared by
+/// `HeaderLines::field` and `BufferedHeaderLines::field`.
+fn write_field<W: Write>(mut line: HeaderLine<W>, name: &str, value: &str) -> std::io::Result<()> {
+    write!(line, "{}: {}", name, value)
+}
+
 /// Writes out the lines in an HTTP response header.
 ///
 /// A response header is made of any number of lines, each terminated by a CRLF, followed
 /// by a final terminating CRLF before the response body begins.
 ///
 /// When this object goes out of scope the header is terminated and the stream is flushed.
-pub struct HeaderLines<W: Write>(W);
+pub struct HeaderLines<W: Write>(Option<W>);
 
 impl<W: Write> HeaderLines<W> {
     /// Create a new `HeaderLines` writing into the given stream.
     pub fn new(sink: W) -> Self {
-        HeaderLines(sink)
+        HeaderLines(Some(sink))
     }
 
     /// Add a new line to the header, which can be written into.
     pub fn line(&mut self) -> HeaderLine<&mut W> {
-        HeaderLine(&mut self.0)
+        HeaderLine(self.0.as_mut().expect("HeaderLines already finished"))
+    }
+
+    /// Terminate the header and hand off to a `ChunkedBody` for writing a
+    /// `Transfer-Encoding: chunked` response body.
+    pub fn into_chunked(mut self) -> ChunkedBody<W> {
+        let mut sink = self.0.take().expect("HeaderLines already finished");
+        terminate(&mut sink);
+        ChunkedBody::new(sink)
+    }
+
+    /// Create a `BufferedHeaderLines` that accumulates all header lines internally
+    /// and performs a single write to `sink`, instead of one write per line.
+    pub fn buffered(sink: W) -> BufferedHeaderLines<W> {
+        BufferedHeaderLines::new(sink)
+    }
+
+    /// Write the status line, filling in the canonical reason phrase for `code` (for
+    /// example, `200` writes as `"200 OK"`). Use `status_with_reason` to supply a
+    /// custom phrase, or for a code with no canonical phrase.
+    pub fn status(&mut self, version: HttpVersion, code: u16) -> std::io::Result<()> {
+        self.status_with_reason(version, code, reason_phrase(code))
+    }
+
+    /// Write the status line with an explicit reason phrase.
+    pub fn status_with_reason(&mut self, version: HttpVersion, code: u16, reason: &str)
+        -> std::io::Result<()>
+    {
+        write!(self.line(), "{} {} {}", version.as_str(), code, reason)
+    }
+
+    /// Write a `name: value` header field line, such as `field::HOST`.
+    pub fn field(&mut self, name: &str, value: &str) -> std::io::Result<()> {
+        write_field(self.line(), name, value)
+    }
+
+    /// Write a `Content-Length` field with the given byte length.
+    pub fn content_length(&mut self, len: usize) -> std::io::Result<()> {
+        self.field(field::CONTENT_LENGTH, &len.to_string())
+    }
+
+    /// Write a `Date` field, formatting `time` as an IMF-fixdate (e.g.
+    /// `"Sun, 06 Nov 1994 08:49:37 GMT"`).
+    pub fn date(&mut self, time: std::time::SystemTime) -> std::io::Result<()> {
+        self.field(field::DATE, &imf_fixdate(time))
     }
 }
 
 impl<W: Write> Drop for HeaderLines<W> {
     fn drop(&mut self) {
         // Output an empty line and flush the buffer.
-        self.line();
-        self.0.flush().is_ok();
+        if let Some(mut sink) = self.0.take() {
+            terminate(&mut sink);
+        }
     }
 }
 
+/// Write a single terminating CRLF into `sink` and flush it.
+fn terminate<W: Write>(sink: &mut W) {
+    HeaderLine(&mut *sink);
+    sink.flush().is_ok();
+}
+
 /// Writes out a header line.
 ///
-/// When this object goes out of scope the line is terminated. The string written into the
-/// line must not contain any CRLFs (`\r\n`.)
+/// When this object goes out of scope the line is terminated. Writes containing any
+/// `\r` or `\n` byte, including a full CRLF, are rejected with an `InvalidData` error instead of being passed through,
+/// since letting them through would let the caller inject extra header lines or terminate
+/// the header early (response splitting).
 pub struct HeaderLine<W: Write>(W);
 
 impl<W: Write> Write for HeaderLine<W> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.0.write(buf) }
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.contains(&b'\r') || buf.contains(&b'\n') {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "header line value contains a CR or LF",
+            ));
+        }
+
+        self.0.write(buf)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> { self.0.flush() }
 }
 
 impl<W: Write> Drop for HeaderLine<W> {
-    fn drop(&mut self) { self.write(&b"\r\n"[..]).is_ok(); }
+    // Write the terminator directly into the inner stream, bypassing the CRLF check in
+    // `write`, since this CRLF is the line's own framing rather than caller-supplied data.
+    fn drop(&mut self) { self.0.write(&b"\r\n"[..]).is_ok(); }
+}
+
+/// Writes out the lines in an HTTP response header, buffering them internally so the
+/// underlying stream only sees a single write, in the spirit of `std::io::BufWriter`.
+///
+/// Get one of these from `HeaderLines::buffered`. When this object goes out of scope
+/// the buffered lines, plus the terminating CRLF, are flushed to the stream and any
+/// write error is discarded; use `finish` instead to observe that error.
+pub struct BufferedHeaderLines<W: Write> {
+    sink: Option<W>,
+    buf: Vec<u8>,
+    content_length_written: bool,
+}
+
+impl<W: Write> BufferedHeaderLines<W> {
+    fn new(sink: W) -> Self {
+        BufferedHeaderLines { sink: Some(sink), buf: Vec::new(), content_length_written: false }
+    }
+
+    /// Add a new line to the header, which can be written into the internal buffer.
+    pub fn line(&mut self) -> HeaderLine<&mut Vec<u8>> {
+        HeaderLine(&mut self.buf)
+    }
+
+    /// Write a `name: value` header field line, such as `field::HOST`. Writing
+    /// `field::CONTENT_LENGTH` this way, rather than through `content_length`, still
+    /// counts toward `finish_with_body_len`'s auto-append check.
+    pub fn field(&mut self, name: &str, value: &str) -> std::io::Result<()> {
+        write_field(self.line(), name, value)?;
+
+        if name.eq_ignore_ascii_case(field::CONTENT_LENGTH) {
+            self.content_length_written = true;
+        }
+
+        Ok(())
+    }
+
+    /// Write a `Content-Length` field with the given byte length.
+    pub fn content_length(&mut self, len: usize) -> std::io::Result<()> {
+        self.field(field::CONTENT_LENGTH, &len.to_string())
+    }
+
+    /// Write a `Date` field, formatting `time` as an IMF-fixdate (e.g.
+    /// `"Sun, 06 Nov 1994 08:49:37 GMT"`).
+    pub fn date(&mut self, time: std::time::SystemTime) -> std::io::Result<()> {
+        self.field(field::DATE, &imf_fixdate(time))
+    }
+
+    /// The number of bytes written into the internal buffer so far, not counting the
+    /// terminating CRLF appended by `finish` or `Drop`.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Whether any lines have been written into the internal buffer yet.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Append the terminating CRLF, flush the buffered header to the underlying
+    /// stream in a single write, and return the stream.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        let mut sink = self.sink.take().expect("BufferedHeaderLines already finished");
+        self.buf.extend_from_slice(b"\r\n");
+        sink.write_all(&self.buf)?;
+        sink.flush()?;
+        Ok(sink)
+    }
+
+    /// Like `finish`, but if no `Content-Length` field has been written through
+    /// `content_length` or `field`, first auto-appends a `Content-Length` field computed
+    /// from `body_len`, the number of bytes of body that will follow the header. Lines
+    /// written through `line` are not checked.
+    pub fn finish_with_body_len(mut self, body_len: usize) -> std::io::Result<W> {
+        if !self.content_length_written {
+            self.content_length(body_len)?;
+        }
+
+        self.finish()
+    }
+}
+
+impl<W: Write> Drop for BufferedHeaderLines<W> {
+    fn drop(&mut self) {
+        if let Some(mut sink) = self.sink.take() {
+            self.buf.extend_from_slice(b"\r\n");
+            sink.write_all(&self.buf).is_ok();
+            sink.flush().is_ok();
+        }
+    }
+}
+
+/// Writes a `Transfer-Encoding: chunked` response body, as described in
+/// [RFC 7230 §4.1](https://tools.ietf.org/html/rfc7230#section-4.1).
+///
+/// Each call to `write` is framed as its own chunk: the ASCII hex length of the
+/// given buffer, a CRLF, the buffer itself, and a trailing CRLF. A write of an
+/// empty buffer is a no-op, since emitting a chunk of length zero would
+/// prematurely terminate the body.
+///
+/// Get one of these from `HeaderLines::into_chunked`. When this object goes out
+/// of scope the terminating `0\r\n\r\n` chunk is written and the stream is
+/// flushed; use `finish` instead to append trailer fields first.
+pub struct ChunkedBody<W: Write>(Option<W>);
+
+impl<W: Write> ChunkedBody<W> {
+    fn new(sink: W) -> Self {
+        ChunkedBody(Some(sink))
+    }
+
+    /// Write the terminating chunk and return a `HeaderLines` wrapping the
+    /// inner stream, so trailer fields can be added before the header's final
+    /// CRLF closes out the response.
+    pub fn finish(mut self) -> std::io::Result<HeaderLines<W>> {
+        let mut sink = self.0.take().expect("ChunkedBody already finished");
+        write!(sink, "0\r\n")?;
+        Ok(HeaderLines::new(sink))
+    }
+}
+
+impl<W: Write> Write for ChunkedBody<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let sink = self.0.as_mut().expect("ChunkedBody already finished");
+        write!(sink, "{:x}\r\n", buf.len())?;
+        sink.write_all(buf)?;
+        write!(sink, "\r\n")?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.as_mut().expect("ChunkedBody already finished").flush()
+    }
+}
+
+impl<W: Write> Drop for ChunkedBody<W> {
+    fn drop(&mut self) {
+        if let Some(mut sink) = self.0.take() {
+            write!(sink, "0\r\n\r\n").is_ok();
+            sink.flush().is_ok();
+        }
+    }
+}
+
+/// An error encountered while parsing a header with `HeaderReader`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderError {
+    /// `buf` doesn't yet contain the terminating blank line; read more bytes from the
+    /// stream and retry once more data has arrived.
+    Truncated,
+    /// A line had no `:` separator, or contained a bare CR or LF outside of its own
+    /// line terminator.
+    Malformed,
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            HeaderError::Truncated => write!(f, "header is truncated"),
+            HeaderError::Malformed => write!(f, "header line is malformed"),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// Reads the start line and field lines of an HTTP header out of a byte buffer,
+/// stopping exactly at the boundary with the body. This is the inverse of
+/// `HeaderLines`: where `HeaderLines::line` hands out a line to write into,
+/// `HeaderReader::field` hands back a parsed `(name, value)` pair.
+///
+/// Because `buf` may be a prefix of a larger incremental read (e.g. a partially
+/// filled socket buffer), `start_line` and `field` return `HeaderError::Truncated`
+/// rather than panicking or blocking when the CRLF ending the next line hasn't arrived
+/// yet; the caller can read more bytes and retry. Lines that are already complete are
+/// returned even if the header's terminating blank line hasn't arrived.
+pub struct HeaderReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+/// A `(name, value)` header field pair, borrowed from the buffer a `HeaderReader` is
+/// parsing.
+pub type Field<'a> = (&'a [u8], &'a [u8]);
+
+impl<'a> HeaderReader<'a> {
+    /// Create a reader over `buf`, which may hold a partial or complete header.
+    pub fn new(buf: &'a [u8]) -> Self {
+        HeaderReader { buf, pos: 0 }
+    }
+
+    /// Parse the start line, e.g. `b"HTTP/1.1 200 OK"` or `b"GET / HTTP/1.1"`.
+    ///
+    /// This must be called exactly once, before any calls to `field`.
+    pub fn start_line(&mut self) -> Result<&'a [u8], HeaderError> {
+        self.next_line()?.ok_or(HeaderError::Malformed)
+    }
+
+    /// Parse the next `name: value` field line, or `Ok(None)` once the header's
+    /// terminating blank line has been reached.
+    pub fn field(&mut self) -> Result<Option<Field<'a>>, HeaderError> {
+        let line = match self.next_line()? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+
+        let colon = line.iter().position(|&b| b == b':').ok_or(HeaderError::Malformed)?;
+        let name = &line[..colon];
+        let value = trim_leading_space(&line[colon + 1..]);
+
+        Ok(Some((name, value)))
+    }
+
+    /// The offset into `buf` of the first byte of the body, i.e. the byte right
+    /// after the header's terminating blank line, once `field` has returned
+    /// `Ok(None)`. Before that, this is the offset of the next unparsed line.
+    pub fn body_offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Pull the next CRLF-terminated line out of the buffer, advancing past it.
+    /// Returns `Ok(None)` for the terminating blank line.
+    fn next_line(&mut self) -> Result<Option<&'a [u8]>, HeaderError> {
+        let rest = &self.buf[self.pos..];
+        let idx = rest.windows(2).position(|w| w == b"\r\n").ok_or(HeaderError::Truncated)?;
+        let line = &rest[..idx];
+
+        if line.contains(&b'\r') || line.contains(&b'\n') {
+            return Err(HeaderError::Malformed);
+        }
+
+        self.pos += idx + 2;
+
+        if line.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(line))
+        }
+    }
+}
+
+/// Strip leading spaces from a field value, as in `"name:   value"`.
+fn trim_leading_space(buf: &[u8]) -> &[u8] {
+    match buf.iter().position(|&b| b != b' ') {
+        Some(i) => &buf[i..],
+        None => &buf[buf.len()..],
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +521,19 @@ mod test {
         assert_eq!(&buf[..], b"ABC: DEF 42\r\n");
     }
 
+    #[test]
+    fn test_header_line_rejects_crlf() {
+        let mut buf = [0u8; 13];
+        let mut c = Cursor::new(&mut buf[..]);
+        let mut h = HeaderLine(&mut c);
+
+        assert_eq!(
+            h.write(b"injected\r\nHost: evil.com").unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData,
+        );
+        assert_eq!(h.write(b"bare\nonly").unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
     #[test]
     fn test_header_lines() {
         let mut buf = [0u8; 30];
@@ -103,4 +548,158 @@ mod test {
 
         assert_eq!(&buf[..], b"header: value\r\nfield: 1337\r\n\r\n");
     }
+
+    #[test]
+    fn test_buffered_header_lines() {
+        let mut buf = [0u8; 30];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::buffered(&mut c);
+
+            write!(h.line(), "header: value").unwrap();
+            write!(h.line(), "field: {}", 1337).unwrap();
+
+            assert_eq!(h.len(), 28);
+            h.finish().unwrap();
+        }
+
+        assert_eq!(&buf[..], b"header: value\r\nfield: 1337\r\n\r\n");
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut buf = [0u8; 35];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::new(&mut c);
+
+            h.status(HttpVersion::Http11, 200).unwrap();
+            write!(h.line(), "Host: iana.org").unwrap();
+        }
+
+        assert_eq!(&buf[..], b"HTTP/1.1 200 OK\r\nHost: iana.org\r\n\r\n");
+    }
+
+    #[test]
+    fn test_status_line_with_reason() {
+        let mut buf = [0u8; 21];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::new(&mut c);
+
+            h.status_with_reason(HttpVersion::Http10, 451, "Nope").unwrap();
+        }
+
+        assert_eq!(&buf[..], b"HTTP/1.0 451 Nope\r\n\r\n");
+    }
+
+    #[test]
+    fn test_field_helpers() {
+        let mut buf = [0u8; 77];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::new(&mut c);
+
+            h.field(field::HOST, "iana.org").unwrap();
+            h.content_length(1337).unwrap();
+            h.date(std::time::UNIX_EPOCH + std::time::Duration::from_secs(784111777)).unwrap();
+        }
+
+        assert_eq!(
+            &buf[..],
+            &b"Host: iana.org\r\nContent-Length: 1337\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n"[..],
+        );
+    }
+
+    #[test]
+    fn test_imf_fixdate() {
+        assert_eq!(
+            imf_fixdate(std::time::UNIX_EPOCH + std::time::Duration::from_secs(784111777)),
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+        );
+        assert_eq!(imf_fixdate(std::time::UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_buffered_header_lines_auto_content_length() {
+        let mut buf = [0u8; 37];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::buffered(&mut c);
+
+            write!(h.line(), "Host: iana.org").unwrap();
+            h.finish_with_body_len(5).unwrap();
+        }
+
+        assert_eq!(&buf[..], b"Host: iana.org\r\nContent-Length: 5\r\n\r\n");
+    }
+
+    #[test]
+    fn test_buffered_header_lines_manual_content_length_field_skips_auto_append() {
+        let mut buf = [0u8; 21];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut h = HeaderLines::buffered(&mut c);
+
+            h.field(field::CONTENT_LENGTH, "5").unwrap();
+            h.finish_with_body_len(5).unwrap();
+        }
+
+        assert_eq!(&buf[..], b"Content-Length: 5\r\n\r\n");
+    }
+
+    #[test]
+    fn test_header_reader() {
+        let data = b"HTTP/1.1 200 OK\r\nHost: iana.org\r\nX-Empty:\r\n\r\nhello";
+        let mut r = HeaderReader::new(&data[..]);
+
+        assert_eq!(r.start_line().unwrap(), &b"HTTP/1.1 200 OK"[..]);
+        assert_eq!(r.field().unwrap(), Some((&b"Host"[..], &b"iana.org"[..])));
+        assert_eq!(r.field().unwrap(), Some((&b"X-Empty"[..], &b""[..])));
+        assert_eq!(r.field().unwrap(), None);
+        assert_eq!(&data[r.body_offset()..], b"hello");
+    }
+
+    #[test]
+    fn test_header_reader_truncated() {
+        let data = b"HTTP/1.1 200 OK\r\nHost: iana.org\r\n";
+        let mut r = HeaderReader::new(&data[..]);
+
+        assert_eq!(r.start_line().unwrap(), &b"HTTP/1.1 200 OK"[..]);
+        assert_eq!(r.field().unwrap(), Some((&b"Host"[..], &b"iana.org"[..])));
+        assert_eq!(r.field().unwrap_err(), HeaderError::Truncated);
+    }
+
+    #[test]
+    fn test_header_reader_malformed() {
+        let data = b"HTTP/1.1 200 OK\r\nBogusLine\r\n\r\n";
+        let mut r = HeaderReader::new(&data[..]);
+
+        r.start_line().unwrap();
+        assert_eq!(r.field().unwrap_err(), HeaderError::Malformed);
+    }
+
+    #[test]
+    fn test_chunked_body() {
+        let mut buf = [0u8; 23];
+
+        {
+            let mut c = Cursor::new(&mut buf[..]);
+            let mut body = HeaderLines::new(&mut c).into_chunked();
+
+            write!(&mut body, "hello").unwrap();
+            body.write(&b""[..]).unwrap();
+            write!(&mut body, "!").unwrap();
+
+            body.finish().unwrap();
+        }
+
+        assert_eq!(&buf[..], b"\r\n5\r\nhello\r\n1\r\n!\r\n0\r\n\r\n");
+    }
 }